@@ -5,22 +5,24 @@ use rand_chacha::ChaCha20Rng;
 use uuid::Uuid;
 
 fn neighbors(x: u8, y: u8, h_size: u8, v_size: u8) -> impl Iterator<Item = (u8, u8)> {
-    let x_as_i8 = x as i8;
-    let y_as_i8 = y as i8;
-    let h_size_as_i8 = h_size as i8;
-    let v_size_as_i8 = v_size as i8;
-    (-1_i8..=1).flat_map(move |dx| {
-        (-1_i8..=1).filter_map(move |dy| {
+    // i16 comfortably holds a u8 coordinate plus or minus 1, so the bound
+    // checks below never overflow, unlike i8 which wraps for x/y >= 127.
+    let x_as_i16 = x as i16;
+    let y_as_i16 = y as i16;
+    let h_size_as_i16 = h_size as i16;
+    let v_size_as_i16 = v_size as i16;
+    (-1_i16..=1).flat_map(move |dx| {
+        (-1_i16..=1).filter_map(move |dy| {
             if dx == 0 && dy == 0 {
                 None
-            } else if x_as_i8 + dx >= h_size_as_i8
-                || y_as_i8 + dy >= v_size_as_i8
-                || x_as_i8 + dx < 0
-                || y_as_i8 + dy < 0
+            } else if x_as_i16 + dx >= h_size_as_i16
+                || y_as_i16 + dy >= v_size_as_i16
+                || x_as_i16 + dx < 0
+                || y_as_i16 + dy < 0
             {
                 None
             } else {
-                Some(((x_as_i8 + dx) as u8, (y_as_i8 + dy) as u8))
+                Some(((x_as_i16 + dx) as u8, (y_as_i16 + dy) as u8))
             }
         })
     })
@@ -42,6 +44,12 @@ pub enum TileState {
     Hidden,
     Revealed,
     Flagged,
+    /// The mine that was revealed and caused a loss, as opposed to the other
+    /// mines swept by `reveal_all_mines`.
+    Exploded,
+    /// A non-mine tile that had been flagged, revealed wrong by
+    /// `reveal_all_mines` once the game is lost.
+    WrongFlag,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -96,13 +104,21 @@ impl Tile {
     }
 
     pub fn is_revealed(&self) -> bool {
-        self.state == TileState::Revealed
+        matches!(self.state, TileState::Revealed | TileState::Exploded)
     }
 
     pub fn is_flagged(&self) -> bool {
         self.state == TileState::Flagged
     }
 
+    pub fn is_exploded(&self) -> bool {
+        self.state == TileState::Exploded
+    }
+
+    pub fn is_wrong_flag(&self) -> bool {
+        self.state == TileState::WrongFlag
+    }
+
     pub fn toggle_flag(&mut self) {
         if self.is_flagged() {
             self.state = TileState::Hidden;
@@ -119,11 +135,18 @@ impl Tile {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PendingMines {
+    mine_count: usize,
+    seed: u64,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Minefield {
     h_size: u8,
     v_size: u8,
     tiles: Vec<Tile>,
+    pending_mines: Option<PendingMines>,
 }
 
 impl Minefield {
@@ -132,17 +155,26 @@ impl Minefield {
         Minefield::from_seed(h_size, v_size, density, seed)
     }
 
+    /// Builds a minefield without placing any mine yet. The mines are placed,
+    /// using this seed, around the first tile revealed so that it (and its
+    /// neighbors) can never be a mine. This keeps `from_seed` deterministic
+    /// while guaranteeing a safe opening, as standard Minesweeper does.
     pub fn from_seed(h_size: u8, v_size: u8, density: f32, seed: u64) -> Minefield {
         let minefield_size = h_size as usize * v_size as usize;
-        let mut rng: ChaCha20Rng = ChaCha20Rng::seed_from_u64(seed);
-        let mine_count: usize = ((minefield_size as f32) * density).round() as usize;
-
-        let mut mines: Vec<(u8, u8)> = (0..h_size)
-            .flat_map(move |x| (0..v_size).map(move |y| (x, y)))
-            .collect();
-        mines.shuffle(&mut rng);
+        let mine_count = ((minefield_size as f32) * density).round() as usize;
+        // The first reveal always excludes the clicked tile plus up to its 8
+        // neighbors, so no more than 9 tiles are ever kept mine-free. Capping
+        // here, rather than when the mines are actually placed, means
+        // `total_mines()` already reports the count that will hold once the
+        // first reveal happens, instead of a higher one that then drops.
+        let mine_count = mine_count.min(minefield_size.saturating_sub(9));
 
-        Minefield::new(h_size, v_size, &mines[0..mine_count])
+        Minefield {
+            h_size,
+            v_size,
+            tiles: vec![Tile::hidden_empty(); minefield_size],
+            pending_mines: Some(PendingMines { mine_count, seed }),
+        }
     }
 
     pub fn new(h_size: u8, v_size: u8, mine_indices: &[(u8, u8)]) -> Minefield {
@@ -151,30 +183,60 @@ impl Minefield {
             tiles[tile_index(x, y, h_size)] = Tile::hidden_mine()
         }
 
-        for x in 0..h_size {
-            for y in 0..v_size {
-                if tiles[tile_index(x, y, h_size)].is_empty() {
-                    let no_of_adjacent_mines = neighbors(x, y, h_size, v_size)
+        let mut minefield = Minefield {
+            h_size,
+            v_size,
+            tiles,
+            pending_mines: None,
+        };
+        minefield.compute_adjacents();
+        minefield
+    }
+
+    fn compute_adjacents(&mut self) {
+        for x in 0..self.h_size {
+            for y in 0..self.v_size {
+                if self.tiles[tile_index(x, y, self.h_size)].is_empty() {
+                    let no_of_adjacent_mines = neighbors(x, y, self.h_size, self.v_size)
                         .map(|(neighbor_x, neighbor_y)| {
-                            &tiles[tile_index(neighbor_x, neighbor_y, h_size)]
+                            &self.tiles[tile_index(neighbor_x, neighbor_y, self.h_size)]
                         })
                         .filter(|&tile| tile.is_mine())
                         .count();
 
                     if no_of_adjacent_mines > 0 {
-                        tiles[tile_index(x, y, h_size)].kind = TileKind::Adjacent {
+                        self.tiles[tile_index(x, y, self.h_size)].kind = TileKind::Adjacent {
                             no_of_mines: no_of_adjacent_mines as u8,
                         }
                     }
                 }
             }
         }
+    }
 
-        Minefield {
-            h_size,
-            v_size,
-            tiles,
+    /// Places the mines deferred by `from_seed`, excluding `(x, y)` and its
+    /// neighbors, then recomputes the adjacent mine counts.
+    fn place_pending_mines(&mut self, x: u8, y: u8, mine_count: usize, seed: u64) {
+        let mut rng: ChaCha20Rng = ChaCha20Rng::seed_from_u64(seed);
+        let h_size = self.h_size;
+        let v_size = self.v_size;
+
+        let excluded: Vec<(u8, u8)> = neighbors(x, y, h_size, v_size)
+            .chain(std::iter::once((x, y)))
+            .collect();
+
+        let mut candidates: Vec<(u8, u8)> = (0..h_size)
+            .flat_map(move |cx| (0..v_size).map(move |cy| (cx, cy)))
+            .filter(|pos| !excluded.contains(pos))
+            .collect();
+        candidates.shuffle(&mut rng);
+
+        let mine_count = mine_count.min(candidates.len());
+        for &(mine_x, mine_y) in &candidates[0..mine_count] {
+            self.tiles[tile_index(mine_x, mine_y, self.h_size)] = Tile::hidden_mine();
         }
+
+        self.compute_adjacents();
     }
 
     pub fn h_size(&self) -> u8 {
@@ -185,14 +247,47 @@ impl Minefield {
         self.v_size
     }
 
+    pub fn total_mines(&self) -> usize {
+        match &self.pending_mines {
+            Some(pending) => pending.mine_count,
+            None => self.tiles.iter().filter(|tile| tile.is_mine()).count(),
+        }
+    }
+
+    pub fn flags_placed(&self) -> usize {
+        self.tiles.iter().filter(|tile| tile.is_flagged()).count()
+    }
+
+    /// Mines not yet accounted for by a flag. Can go negative, like the
+    /// classic HUD counter, if the player places more flags than there are
+    /// mines.
+    pub fn mines_remaining(&self) -> i32 {
+        self.total_mines() as i32 - self.flags_placed() as i32
+    }
+
+    /// Reveals `(x, y)` and, if it is empty, cascades the reveal to its
+    /// neighbors. The cascade is driven by an explicit work stack rather than
+    /// recursion, so it runs in bounded stack space regardless of how large
+    /// the open region is.
     pub fn reveal(&mut self, x: u8, y: u8) {
-        let tile = &mut self.tiles[tile_index(x, y, self.h_size)];
-        if tile.is_hidden() {
+        if let Some(PendingMines { mine_count, seed }) = self.pending_mines.take() {
+            self.place_pending_mines(x, y, mine_count, seed);
+        }
+
+        let mut pending = vec![(x, y)];
+        while let Some((tile_x, tile_y)) = pending.pop() {
+            let tile = &mut self.tiles[tile_index(tile_x, tile_y, self.h_size)];
+            if !tile.is_hidden() {
+                continue;
+            }
             tile.reveal();
 
             if tile.is_empty() {
-                for (x, y) in neighbors(x, y, self.h_size, self.v_size) {
-                    self.reveal(x, y);
+                for (neighbor_x, neighbor_y) in neighbors(tile_x, tile_y, self.h_size, self.v_size)
+                {
+                    if self.tiles[tile_index(neighbor_x, neighbor_y, self.h_size)].is_hidden() {
+                        pending.push((neighbor_x, neighbor_y));
+                    }
                 }
             }
         }
@@ -202,6 +297,30 @@ impl Minefield {
         self.tiles[tile_index(x, y, self.h_size)].toggle_flag();
     }
 
+    /// Reveals all hidden, unflagged neighbors of an already revealed numbered
+    /// tile, provided the number of flagged neighbors matches its mine count.
+    pub fn chord(&mut self, x: u8, y: u8) {
+        let tile = &self.tiles[tile_index(x, y, self.h_size)];
+        if !tile.is_revealed() || !tile.is_adjacent() {
+            return;
+        }
+
+        let no_of_adjacent_mines = tile.no_of_adjacent_mine();
+        let neighbor_positions: Vec<(u8, u8)> = neighbors(x, y, self.h_size, self.v_size).collect();
+        let no_of_flagged = neighbor_positions
+            .iter()
+            .filter(|&&(nx, ny)| self.tiles[tile_index(nx, ny, self.h_size)].is_flagged())
+            .count();
+
+        if no_of_flagged as u8 == no_of_adjacent_mines {
+            for (nx, ny) in neighbor_positions {
+                if self.tiles[tile_index(nx, ny, self.h_size)].is_hidden() {
+                    self.reveal(nx, ny);
+                }
+            }
+        }
+    }
+
     pub fn game_status(&self) -> GameStatus {
         let mut status = GameStatus::Won;
         for tile in &self.tiles {
@@ -213,6 +332,33 @@ impl Minefield {
         }
         status
     }
+
+    /// Reveals every remaining mine after a loss, giving visual closure:
+    /// the mine that was actually revealed (and caused the loss) is marked
+    /// as exploded, the rest as plain revealed, and any tile that had been
+    /// flagged but was not a mine is marked as a wrong flag.
+    pub fn reveal_all_mines(&mut self) {
+        for tile in self.tiles.iter_mut() {
+            if tile.is_mine() {
+                if tile.state == TileState::Revealed {
+                    tile.state = TileState::Exploded;
+                } else if tile.is_hidden() {
+                    tile.state = TileState::Revealed;
+                }
+            } else if tile.is_flagged() {
+                tile.state = TileState::WrongFlag;
+            }
+        }
+    }
+
+    /// Flags every remaining hidden mine after a win, giving visual closure.
+    pub fn flag_all_mines(&mut self) {
+        for tile in self.tiles.iter_mut() {
+            if tile.is_mine() && tile.is_hidden() {
+                tile.state = TileState::Flagged;
+            }
+        }
+    }
 }
 
 impl Index<(u8, u8)> for Minefield {
@@ -278,4 +424,126 @@ mod tests {
         let result: Vec<(u8, u8)> = neighbors(0, 2, 3, 3).collect();
         assert_eq!(result, vec![(0, 1), (1, 1), (1, 2)]);
     }
+
+    #[test]
+    fn reveal_all_mines_should_mark_the_detonated_mine_as_exploded_and_reveal_the_rest() {
+        let mines = [(0, 0), (2, 2)];
+        let mut minefield = Minefield::new(3, 3, &mines);
+        minefield.reveal(0, 0);
+
+        minefield.reveal_all_mines();
+
+        assert!(minefield[(0, 0)].is_exploded());
+        assert!(minefield[(2, 2)].is_revealed());
+        assert!(!minefield[(2, 2)].is_exploded());
+    }
+
+    #[test]
+    fn reveal_all_mines_should_mark_incorrectly_flagged_tiles_as_wrong_flag() {
+        let mines = [(0, 0)];
+        let mut minefield = Minefield::new(3, 3, &mines);
+        minefield.toggle_flag(1, 1);
+        minefield.reveal(0, 0);
+
+        minefield.reveal_all_mines();
+
+        assert!(minefield[(1, 1)].is_wrong_flag());
+    }
+
+    #[test]
+    fn flag_all_mines_should_flag_every_hidden_mine() {
+        let mines = [(0, 0), (2, 2)];
+        let mut minefield = Minefield::new(3, 3, &mines);
+
+        minefield.flag_all_mines();
+
+        assert!(minefield[(0, 0)].is_flagged());
+        assert!(minefield[(2, 2)].is_flagged());
+    }
+
+    #[test]
+    fn chord_should_reveal_hidden_neighbors_when_flags_match_adjacent_mines() {
+        let mines = [(0, 0)];
+        let mut minefield = Minefield::new(3, 3, &mines);
+        minefield.toggle_flag(0, 0);
+        minefield.reveal(1, 0);
+
+        minefield.chord(1, 0);
+
+        assert!(minefield[(0, 1)].is_revealed());
+        assert!(minefield[(1, 1)].is_revealed());
+        assert!(minefield[(0, 0)].is_flagged());
+    }
+
+    #[test]
+    fn total_mines_should_count_the_mines_even_before_they_are_placed() {
+        let minefield = Minefield::from_seed(5, 5, 0.2, 42);
+        assert_eq!(minefield.total_mines(), 5);
+    }
+
+    #[test]
+    fn total_mines_should_stay_stable_across_the_first_reveal_even_at_full_density() {
+        let mut minefield = Minefield::from_seed(5, 5, 1.0, 42);
+        let mines_before = minefield.total_mines();
+
+        minefield.reveal(2, 2);
+
+        assert_eq!(minefield.total_mines(), mines_before);
+    }
+
+    #[test]
+    fn mines_remaining_should_subtract_flags_placed_from_total_mines() {
+        let mines = [(0, 0), (1, 1), (2, 2)];
+        let mut minefield = Minefield::new(3, 3, &mines);
+        minefield.toggle_flag(0, 0);
+
+        assert_eq!(minefield.total_mines(), 3);
+        assert_eq!(minefield.flags_placed(), 1);
+        assert_eq!(minefield.mines_remaining(), 2);
+    }
+
+    #[test]
+    fn reveal_should_cascade_through_a_large_empty_field_without_overflowing_the_stack() {
+        let mut minefield = Minefield::new(255, 255, &[]);
+        minefield.reveal(0, 0);
+
+        for x in 0..255 {
+            for y in 0..255 {
+                assert!(minefield[(x, y)].is_revealed());
+            }
+        }
+    }
+
+    #[test]
+    fn from_seed_should_not_place_mines_until_the_first_reveal() {
+        let minefield = Minefield::from_seed(5, 5, 1.0, 42);
+        for x in 0..5 {
+            for y in 0..5 {
+                assert!(!minefield[(x, y)].is_mine());
+            }
+        }
+    }
+
+    #[test]
+    fn from_seed_should_guarantee_the_first_reveal_and_its_neighbors_are_safe() {
+        let mut minefield = Minefield::from_seed(5, 5, 1.0, 42);
+        minefield.reveal(2, 2);
+
+        assert!(!minefield[(2, 2)].is_mine());
+        for (neighbor_x, neighbor_y) in neighbors(2, 2, 5, 5) {
+            assert!(!minefield[(neighbor_x, neighbor_y)].is_mine());
+        }
+    }
+
+    #[test]
+    fn chord_should_do_nothing_when_flags_do_not_match_adjacent_mines() {
+        let mines = [(0, 0)];
+        let mut minefield = Minefield::new(3, 3, &mines);
+        minefield.reveal(1, 0);
+
+        minefield.chord(1, 0);
+
+        assert!(minefield[(0, 1)].is_hidden());
+        assert!(minefield[(1, 1)].is_hidden());
+    }
 }
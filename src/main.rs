@@ -12,8 +12,33 @@ const SPRITES_BYTES: &[u8] = include_bytes!("assets/sprites.png");
 const FONT_SIZE: f32 = 64.0;
 const FONT_SIZE_XL: f32 = 80.0;
 const TILE_SIZE: u32 = 64;
+const HUD_HEIGHT: u32 = TILE_SIZE;
 
 const TEXT_COLOR: Color = Color::from_rgba(74, 74, 74, 255);
+const HUD_BACKGROUND_COLOR: Color = Color::from_rgba(40, 40, 40, 255);
+const SEGMENT_COLOR: Color = Color::from_rgba(220, 30, 30, 255);
+const CURSOR_COLOR: Color = Color::from_rgba(255, 235, 59, 200);
+const CURSOR_THICKNESS: f32 = 4.0;
+
+const DIGIT_WIDTH: f32 = 24.0;
+const DIGIT_HEIGHT: f32 = 40.0;
+const DIGIT_GAP: f32 = 6.0;
+const DIGIT_THICKNESS: f32 = 5.0;
+
+// Segments lit per digit, in [a, b, c, d, e, f, g] order (a = top, going
+// clockwise, g = middle), the usual seven-segment layout.
+const SEVEN_SEGMENT_DIGITS: [[bool; 7]; 10] = [
+    [true, true, true, true, true, true, false],
+    [false, true, true, false, false, false, false],
+    [true, true, false, true, true, false, true],
+    [true, true, true, true, false, false, true],
+    [false, true, true, false, false, true, true],
+    [true, false, true, true, false, true, true],
+    [true, false, true, true, true, true, true],
+    [true, true, true, false, false, false, false],
+    [true, true, true, true, true, true, true],
+    [true, true, true, true, false, true, true],
+];
 
 #[derive(Parser, Debug)]
 #[command(author, version, about)]
@@ -34,7 +59,7 @@ fn window_conf() -> Conf {
     Conf {
         window_title: "Minesweeper".to_owned(),
         window_width: args.width as i32 * TILE_SIZE as i32, // width in pixels
-        window_height: args.height as i32 * TILE_SIZE as i32, // height in pixels
+        window_height: args.height as i32 * TILE_SIZE as i32 + HUD_HEIGHT as i32, // height in pixels, plus the HUD row
         window_resizable: false,
         ..Default::default()
     }
@@ -55,7 +80,16 @@ fn draw_text_centered(
     draw_text(text, text_x, text_y, font_size, color);
 }
 
-/// x 0 to 64 is 0, x 65 to 128 is 2, ....
+/// (width, height, mine count) for the classic Beginner/Intermediate/Expert
+/// difficulty presets, selectable at runtime with the 1/2/3 keys.
+const PRESETS: [(u8, u8, u32); 3] = [(9, 9, 10), (16, 16, 40), (30, 16, 99)];
+
+fn preset_density(width: u8, height: u8, mine_count: u32) -> f32 {
+    mine_count as f32 / (width as u32 * height as u32) as f32
+}
+
+/// x 0 to 63 is 0, x 64 to 127 is 1, .... `point` is relative to the board,
+/// i.e. with the HUD height already subtracted from its y coordinate.
 fn screen_point_to_tile_index(point: Vec2) -> (u8, u8) {
     let x = point.x / TILE_SIZE as f32;
     let y = point.y / TILE_SIZE as f32;
@@ -63,6 +97,68 @@ fn screen_point_to_tile_index(point: Vec2) -> (u8, u8) {
     (x as u8, y as u8)
 }
 
+fn draw_seven_segment_digit(digit: u32, x: f32, y: f32) {
+    let segments = SEVEN_SEGMENT_DIGITS[digit as usize];
+    let t = DIGIT_THICKNESS;
+    let w = DIGIT_WIDTH;
+    let h = DIGIT_HEIGHT;
+    let half = h / 2.0 - t * 1.5;
+
+    if segments[0] {
+        draw_rectangle(x + t, y, w - 2.0 * t, t, SEGMENT_COLOR); // a: top
+    }
+    if segments[1] {
+        draw_rectangle(x + w - t, y + t, t, half, SEGMENT_COLOR); // b: top-right
+    }
+    if segments[2] {
+        draw_rectangle(x + w - t, y + h / 2.0 + t * 0.5, t, half, SEGMENT_COLOR);
+        // c: bottom-right
+    }
+    if segments[3] {
+        draw_rectangle(x + t, y + h - t, w - 2.0 * t, t, SEGMENT_COLOR); // d: bottom
+    }
+    if segments[4] {
+        draw_rectangle(x, y + h / 2.0 + t * 0.5, t, half, SEGMENT_COLOR); // e: bottom-left
+    }
+    if segments[5] {
+        draw_rectangle(x, y + t, t, half, SEGMENT_COLOR); // f: top-left
+    }
+    if segments[6] {
+        draw_rectangle(x + t, y + h / 2.0 - t / 2.0, w - 2.0 * t, t, SEGMENT_COLOR);
+        // g: middle
+    }
+}
+
+/// Renders `value` as the classic fixed-width, three-digit seven-segment
+/// display used for the mines-remaining and timer HUD counters. A negative
+/// value shows a minus sign in place of its leading digit.
+fn draw_seven_segment(value: i32, x: f32, y: f32) {
+    let clamped = value.clamp(-99, 999);
+    let negative = clamped < 0;
+    let mut magnitude = clamped.unsigned_abs();
+
+    let mut digits = [0_u32; 3];
+    for digit in digits.iter_mut().rev() {
+        *digit = magnitude % 10;
+        magnitude /= 10;
+    }
+
+    for (i, &digit) in digits.iter().enumerate() {
+        let digit_x = x + i as f32 * (DIGIT_WIDTH + DIGIT_GAP);
+        if negative && i == 0 {
+            draw_rectangle(
+                digit_x,
+                y + DIGIT_HEIGHT / 2.0 - DIGIT_THICKNESS / 2.0,
+                DIGIT_WIDTH,
+                DIGIT_THICKNESS,
+                SEGMENT_COLOR,
+            );
+        } else {
+            draw_seven_segment_digit(digit, digit_x, y);
+        }
+    }
+}
+
 #[macroquad::main(window_conf)]
 async fn main() {
     env_logger::init();
@@ -81,33 +177,105 @@ async fn main() {
         TILE_SIZE as f32,
     );
 
-    let mut minefield = Minefield::random(args.width, args.height, args.density);
+    let mut board_width = args.width;
+    let mut board_height = args.height;
+    let mut board_density = args.density;
+    let mut minefield = Minefield::random(board_width, board_height, board_density);
+
+    let mut start_time: Option<f64> = None;
+    let mut frozen_elapsed_seconds: Option<u32> = None;
+    let mut cursor: (u8, u8) = (0, 0);
 
     loop {
+        let mut reset_requested = is_key_pressed(KeyCode::R);
+
+        let preset_keys = [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3];
+        for (preset_key, &(width, height, mine_count)) in preset_keys.iter().zip(PRESETS.iter()) {
+            if is_key_pressed(*preset_key) {
+                board_width = width;
+                board_height = height;
+                board_density = preset_density(width, height, mine_count);
+                reset_requested = true;
+            }
+        }
+
+        if reset_requested {
+            minefield = Minefield::random(board_width, board_height, board_density);
+            request_new_screen_size(
+                board_width as f32 * TILE_SIZE as f32,
+                board_height as f32 * TILE_SIZE as f32 + HUD_HEIGHT as f32,
+            );
+            start_time = None;
+            frozen_elapsed_seconds = None;
+            cursor = (0, 0);
+        }
+
+        let status = minefield.game_status();
+
+        // True only on the single frame the game transitions out of `Going`,
+        // which is also when the timer freezes below.
+        let just_ended =
+            status != GameStatus::Going && start_time.is_some() && frozen_elapsed_seconds.is_none();
+        if just_ended {
+            match status {
+                GameStatus::Lost => minefield.reveal_all_mines(),
+                GameStatus::Won => minefield.flag_all_mines(),
+                GameStatus::Going => {}
+            }
+        }
+
+        let elapsed_seconds = match (frozen_elapsed_seconds, start_time) {
+            (Some(frozen), _) => frozen,
+            (None, Some(start)) => (get_time() - start) as u32,
+            (None, None) => 0,
+        };
+        if just_ended {
+            frozen_elapsed_seconds = Some(elapsed_seconds);
+        }
+
         // TODO extract render_minefield
         clear_background(WHITE);
+
+        draw_rectangle(
+            0.0,
+            0.0,
+            screen_width(),
+            HUD_HEIGHT as f32,
+            HUD_BACKGROUND_COLOR,
+        );
+        draw_seven_segment(minefield.mines_remaining(), 8.0, 12.0);
+        draw_seven_segment(
+            elapsed_seconds as i32,
+            screen_width() - 8.0 - 3.0 * DIGIT_WIDTH - 2.0 * DIGIT_GAP,
+            12.0,
+        );
+
         for i in 0..minefield.h_size() {
             for j in 0..minefield.v_size() {
                 let tile = &minefield[(i, j)];
 
                 let x = i as f32 * TILE_SIZE as f32;
-                let y = j as f32 * TILE_SIZE as f32;
+                let y = HUD_HEIGHT as f32 + j as f32 * TILE_SIZE as f32;
 
-                let rect = if tile.is_hidden() {
-                    hidden_rect
+                let (rect, tint) = if tile.is_hidden() {
+                    (hidden_rect, WHITE)
+                } else if tile.is_exploded() {
+                    (mine_rect, RED)
+                } else if tile.is_wrong_flag() {
+                    (flag_rect, GRAY)
                 } else if tile.is_flagged() {
-                    flag_rect
+                    (flag_rect, WHITE)
                 } else if tile.is_mine() {
-                    mine_rect
+                    (mine_rect, WHITE)
                 } else {
-                    empty_rect
+                    (empty_rect, WHITE)
                 };
 
                 draw_texture_ex(
                     &sprites_texture,
                     x,
                     y,
-                    WHITE,
+                    tint,
                     DrawTextureParams {
                         dest_size: Some(Vec2::new(TILE_SIZE as f32, TILE_SIZE as f32)), // output size
                         source: Some(rect),
@@ -129,7 +297,16 @@ async fn main() {
             }
         }
 
-        match minefield.game_status() {
+        draw_rectangle_lines(
+            cursor.0 as f32 * TILE_SIZE as f32,
+            HUD_HEIGHT as f32 + cursor.1 as f32 * TILE_SIZE as f32,
+            TILE_SIZE as f32,
+            TILE_SIZE as f32,
+            CURSOR_THICKNESS,
+            CURSOR_COLOR,
+        );
+
+        match status {
             GameStatus::Lost => draw_text_centered(
                 "YOU LOST!",
                 0.0,
@@ -149,19 +326,66 @@ async fn main() {
                 RED,
             ),
             GameStatus::Going => {
+                if is_key_pressed(KeyCode::Left) {
+                    cursor.0 = cursor.0.saturating_sub(1);
+                }
+                if is_key_pressed(KeyCode::Right) {
+                    cursor.0 = (cursor.0 + 1).min(minefield.h_size() - 1);
+                }
+                if is_key_pressed(KeyCode::Up) {
+                    cursor.1 = cursor.1.saturating_sub(1);
+                }
+                if is_key_pressed(KeyCode::Down) {
+                    cursor.1 = (cursor.1 + 1).min(minefield.v_size() - 1);
+                }
+
+                if is_key_pressed(KeyCode::F) {
+                    minefield.toggle_flag(cursor.0, cursor.1);
+                }
+
+                if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::Space) {
+                    if start_time.is_none() {
+                        start_time = Some(get_time());
+                    }
+                    let tile = &minefield[cursor];
+                    if tile.is_revealed() && tile.is_adjacent() {
+                        minefield.chord(cursor.0, cursor.1);
+                    } else {
+                        minefield.reveal(cursor.0, cursor.1);
+                    }
+                }
+
                 if is_mouse_button_pressed(MouseButton::Right) {
                     let mouse_pos: Vec2 = mouse_position().into();
-                    let (tile_x, tile_y) = screen_point_to_tile_index(mouse_pos);
-                    if tile_x < minefield.h_size() && tile_y < minefield.v_size() {
-                        minefield.toggle_flag(tile_x, tile_y);
+                    if mouse_pos.y >= HUD_HEIGHT as f32 {
+                        let (tile_x, tile_y) = screen_point_to_tile_index(Vec2::new(
+                            mouse_pos.x,
+                            mouse_pos.y - HUD_HEIGHT as f32,
+                        ));
+                        if tile_x < minefield.h_size() && tile_y < minefield.v_size() {
+                            minefield.toggle_flag(tile_x, tile_y);
+                        }
                     }
                 }
 
                 if is_mouse_button_pressed(MouseButton::Left) {
                     let mouse_pos: Vec2 = mouse_position().into();
-                    let (tile_x, tile_y) = screen_point_to_tile_index(mouse_pos);
-                    if tile_x < minefield.h_size() && tile_y < minefield.v_size() {
-                        minefield.reveal(tile_x, tile_y);
+                    if mouse_pos.y >= HUD_HEIGHT as f32 {
+                        let (tile_x, tile_y) = screen_point_to_tile_index(Vec2::new(
+                            mouse_pos.x,
+                            mouse_pos.y - HUD_HEIGHT as f32,
+                        ));
+                        if tile_x < minefield.h_size() && tile_y < minefield.v_size() {
+                            if start_time.is_none() {
+                                start_time = Some(get_time());
+                            }
+                            let tile = &minefield[(tile_x, tile_y)];
+                            if tile.is_revealed() && tile.is_adjacent() {
+                                minefield.chord(tile_x, tile_y);
+                            } else {
+                                minefield.reveal(tile_x, tile_y);
+                            }
+                        }
                     }
                 }
             }